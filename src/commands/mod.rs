@@ -0,0 +1,4 @@
+pub mod prune;
+pub mod pulse;
+pub mod status;
+pub mod sweep;
@@ -1,28 +1,38 @@
-use anyhow::{Result, Context};
+use anyhow::{bail, Result, Context};
 use colored::*;
-use git2::{Repository, BranchType};
+use git2::{BranchType, Oid, Repository};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::io::Write;
+use std::path::Path;
 use std::process::Command;
 
-pub fn run() -> Result<()> {
-    println!("{}", "🌿 Checking for stale local branches...".bright_blue());
+/// Runs prune. Returns the JSON result when `json` is set (the caller is
+/// responsible for printing it, so multi-repo mode can wrap it with a repo
+/// label); otherwise prints the colored output itself and returns `None`.
+pub fn run(repo_path: &Path, json: bool, assume_yes: bool) -> Result<Option<serde_json::Value>> {
+    if !json {
+        println!("{}", "🌿 Checking for stale local branches...".bright_blue());
+    }
 
-    // open current repo
-    let repo = Repository::discover(".")
+    // open the target repo
+    let repo = Repository::discover(repo_path)
         .context("Not a git repository (or any of the parent directories)")?;
 
     // fetch remote info (silent)
     Command::new("git")
+        .current_dir(repo_path)
         .args(["fetch", "--prune", "--quiet"])
         .output()
         .ok();
 
-    // list all local branches
-    let branches = repo.branches(Some(BranchType::Local))?;
-    let mut stale_branches = Vec::new();
+    let default_branch_tip = default_branch_oid(&repo);
+
+    // merged: tip is reachable from main/master (safe to delete)
+    // gone: has a configured upstream that no longer resolves after the fetch --prune above
+    let mut merged_branches = Vec::new();
+    let mut gone_branches = Vec::new();
 
-    for branch_result in branches {
+    for branch_result in repo.branches(Some(BranchType::Local))? {
         let (branch, _) = branch_result?;
         let name = branch.name()?.unwrap_or("").to_string();
 
@@ -30,87 +40,207 @@ pub fn run() -> Result<()> {
             continue;
         }
 
-        // check if remote branch exists
-        let remote_ref = format!("refs/remotes/origin/{}", name);
-        if repo.find_reference(&remote_ref).is_err() {
-            stale_branches.push(name);
+        if is_merged(&repo, default_branch_tip, branch.get().target()) {
+            merged_branches.push(name);
+            continue;
+        }
+
+        if is_gone(&repo, &name, &branch) {
+            gone_branches.push(name);
         }
     }
 
-    if stale_branches.is_empty() {
+    if merged_branches.is_empty() && gone_branches.is_empty() {
+        if json {
+            return Ok(Some(serde_json::json!({"merged": [], "gone": [], "deleted": [], "skipped": []})));
+        }
         println!("{}", "✅ No stale branches found.".green());
-        return Ok(());
+        return Ok(None);
+    }
+
+    let current_branch = get_current_branch(repo_path);
+
+    if !json {
+        print_category("✅ Merged (safe to delete)", &merged_branches, &current_branch);
+        print_category("⚠️  Gone (upstream missing, unmerged)", &gone_branches, &current_branch);
+    }
+
+    if !assume_yes && json {
+        // A prompt on stdout would corrupt machine-readable output and hang
+        // non-interactive callers, so --json requires --yes instead of asking.
+        bail!("--json requires --yes for destructive commands (no interactive prompt in JSON mode)");
+    }
+
+    let mut deleted = Vec::new();
+    let mut skipped = Vec::new();
+
+    confirm_and_delete(
+        repo_path,
+        "merged",
+        &merged_branches,
+        &current_branch,
+        json,
+        assume_yes,
+        &mut deleted,
+        &mut skipped,
+    )?;
+    confirm_and_delete(
+        repo_path,
+        "gone",
+        &gone_branches,
+        &current_branch,
+        json,
+        assume_yes,
+        &mut deleted,
+        &mut skipped,
+    )?;
+
+    if json {
+        return Ok(Some(serde_json::json!({
+            "merged": merged_branches,
+            "gone": gone_branches,
+            "deleted": deleted,
+            "skipped": skipped,
+        })));
+    }
+
+    println!("{}", "✅ Done. Your repo is now tidy.".green());
+
+    Ok(None)
+}
+
+/// A branch is "merged" when its tip is reachable from main/master — safe to delete.
+fn is_merged(repo: &Repository, default_tip: Option<Oid>, branch_tip: Option<Oid>) -> bool {
+    match (default_tip, branch_tip) {
+        (Some(default_oid), Some(branch_oid)) => {
+            branch_oid == default_oid
+                || repo.graph_descendant_of(default_oid, branch_oid).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Classify a branch as "gone": it has a configured upstream, but that
+/// upstream no longer resolves to a remote-tracking ref (e.g. the remote
+/// branch was deleted and `fetch --prune` cleaned up our local copy of it).
+fn is_gone(repo: &Repository, name: &str, branch: &git2::Branch) -> bool {
+    if branch.upstream().is_ok() {
+        return false;
     }
-    
-    // get current branch to avoid deleting it
-    let current_branch = get_current_branch();
-    
-    println!("{}", format!("Found {} branches to delete:", stale_branches.len()).yellow());
-    
-    // Display branches as a tree
-    for (i, branch) in stale_branches.iter().enumerate() {
+
+    repo.branch_upstream_name(&format!("refs/heads/{}", name)).is_ok()
+}
+
+fn default_branch_oid(repo: &Repository) -> Option<Oid> {
+    repo.find_branch("main", BranchType::Local)
+        .or_else(|_| repo.find_branch("master", BranchType::Local))
+        .ok()?
+        .get()
+        .target()
+}
+
+fn print_category(title: &str, branches: &[String], current_branch: &Option<String>) {
+    if branches.is_empty() {
+        return;
+    }
+
+    println!("{}", format!("\n{} ({}):", title, branches.len()).yellow());
+
+    for (i, branch) in branches.iter().enumerate() {
         let is_current = Some(branch.as_str()) == current_branch.as_deref();
         let branch_display = if is_current {
             format!("{} (current branch)", branch).yellow().to_string()
         } else {
             branch.bright_white().to_string()
         };
-        
-        if i == stale_branches.len() - 1 {
+
+        if i == branches.len() - 1 {
             println!("└── {}", branch_display);
         } else {
             println!("├── {}", branch_display);
         }
     }
-    
-    // Ask for confirmation
-    print!("{}", "\nDo you want to delete these branches? [y/N]: ".bright_yellow());
-    std::io::stdout().flush().unwrap(); // Ensure prompt is displayed before reading input
-    
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input)?;
-    
-    if !input.trim().eq_ignore_ascii_case("y") {
-        println!("{}", "Operation cancelled.".yellow());
+}
+
+#[allow(clippy::too_many_arguments)]
+fn confirm_and_delete(
+    repo_path: &Path,
+    label: &str,
+    branches: &[String],
+    current_branch: &Option<String>,
+    json: bool,
+    assume_yes: bool,
+    deleted: &mut Vec<String>,
+    skipped: &mut Vec<String>,
+) -> Result<()> {
+    if branches.is_empty() {
         return Ok(());
     }
 
-    let bar = ProgressBar::new(stale_branches.len() as u64);
-    bar.set_style(
-        ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}"
-        )
-        .unwrap()
-        .progress_chars("#>-"),
-    );
+    if !assume_yes {
+        print!(
+            "{}",
+            format!("\nDelete {} {} branch(es)? [y/N]: ", branches.len(), label).bright_yellow()
+        );
+        std::io::stdout().flush().unwrap(); // Ensure prompt is displayed before reading input
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            skipped.extend(branches.iter().cloned());
+            return Ok(());
+        }
+    }
+
+    let bar = if json {
+        None
+    } else {
+        let bar = ProgressBar::new(branches.len() as u64);
+        bar.set_style(
+            ProgressStyle::with_template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+        );
+        Some(bar)
+    };
 
-    for branch in &stale_branches {
+    for branch in branches {
         if Some(branch.as_str()) == current_branch.as_deref() {
-            bar.println(format!(
-                "{} Skipping current branch '{}'",
-                "⚠️".yellow(),
-                branch
-            ));
-            bar.inc(1);
+            if let Some(bar) = &bar {
+                bar.println(format!("{} Skipping current branch '{}'", "⚠️".yellow(), branch));
+                bar.inc(1);
+            }
+            skipped.push(branch.clone());
             continue;
         }
 
-        bar.set_message(format!("Deleting {}", branch));
+        if let Some(bar) = &bar {
+            bar.set_message(format!("Deleting {}", branch));
+        }
         Command::new("git")
+            .current_dir(repo_path)
             .args(["branch", "-D", branch])
             .output()
             .with_context(|| format!("Failed to delete branch {}", branch))?;
-        bar.inc(1);
+        deleted.push(branch.clone());
+        if let Some(bar) = &bar {
+            bar.inc(1);
+        }
     }
 
-    bar.finish_with_message("✨ All stale branches pruned!");
-    println!("{}", "✅ Done. Your repo is now tidy.".green());
+    if let Some(bar) = bar {
+        bar.finish_and_clear();
+    }
 
     Ok(())
 }
 
-fn get_current_branch() -> Option<String> {
+fn get_current_branch(repo_path: &Path) -> Option<String> {
     let output = Command::new("git")
+        .current_dir(repo_path)
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .output()
         .ok()?;
@@ -121,4 +251,87 @@ fn get_current_branch() -> Option<String> {
     } else {
         None
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{RepositoryInitOptions, Signature};
+
+    /// Init a repo with a single commit on `main` and return it alongside that commit's oid.
+    fn init_repo() -> (tempfile::TempDir, Repository, Oid) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut opts = RepositoryInitOptions::new();
+        opts.initial_head("main");
+        let repo = Repository::init_opts(dir.path(), &opts).unwrap();
+
+        let oid = commit(&repo, "root commit");
+        (dir, repo, oid)
+    }
+
+    fn commit(repo: &Repository, message: &str) -> Oid {
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree_oid = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_oid).unwrap();
+
+        let parents: Vec<_> = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parent_refs: Vec<&_> = parents.iter().collect();
+
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    #[test]
+    fn merged_branch_is_reachable_from_default() {
+        let (_dir, repo, root_oid) = init_repo();
+        repo.branch("feature", &repo.find_commit(root_oid).unwrap(), false).unwrap();
+
+        // advance main past the branch point, so `feature`'s tip becomes an ancestor of main
+        let tip = commit(&repo, "second commit on main");
+
+        let branch = repo.find_branch("feature", BranchType::Local).unwrap();
+        assert!(is_merged(&repo, Some(tip), branch.get().target()));
+    }
+
+    #[test]
+    fn unmerged_branch_is_not_reachable_from_default() {
+        let (_dir, repo, root_oid) = init_repo();
+        let default_tip = commit(&repo, "second commit on main");
+
+        // branch off the root and diverge with its own commit, never merged back
+        repo.branch("wip", &repo.find_commit(root_oid).unwrap(), false).unwrap();
+        repo.set_head("refs/heads/wip").unwrap();
+        let branch_tip = commit(&repo, "diverging work");
+        repo.set_head("refs/heads/main").unwrap();
+
+        assert!(!is_merged(&repo, Some(default_tip), Some(branch_tip)));
+    }
+
+    #[test]
+    fn branch_with_dangling_upstream_is_gone() {
+        let (_dir, repo, root_oid) = init_repo();
+        repo.branch("ghost", &repo.find_commit(root_oid).unwrap(), false).unwrap();
+
+        // Configure an upstream that doesn't correspond to any remote-tracking ref,
+        // simulating a branch whose remote was deleted and pruned away.
+        repo.remote("origin", "https://example.invalid/repo.git").unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("branch.ghost.remote", "origin").unwrap();
+        config.set_str("branch.ghost.merge", "refs/heads/ghost").unwrap();
+
+        let branch = repo.find_branch("ghost", BranchType::Local).unwrap();
+        assert!(is_gone(&repo, "ghost", &branch));
+    }
+
+    #[test]
+    fn branch_without_upstream_is_not_gone() {
+        let (_dir, repo, root_oid) = init_repo();
+        repo.branch("local-only", &repo.find_commit(root_oid).unwrap(), false).unwrap();
+
+        let branch = repo.find_branch("local-only", BranchType::Local).unwrap();
+        assert!(!is_gone(&repo, "local-only", &branch));
+    }
+}
@@ -1,119 +1,128 @@
 use anyhow::{Result, Context};
 use colored::*;
-use std::collections::{HashMap};
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 use indicatif::{ProgressBar, ProgressStyle};
 
-pub fn run(rank_by_lines: bool) -> Result<()> {
+// Unlikely-to-collide separators used to pull one record per commit (including
+// its full body, for Co-authored-by trailers) out of a single `git log` call.
+const RECORD_SEP: &str = "\u{1}";
+const BODY_SEP: &str = "\u{2}";
+const END_SEP: &str = "\u{3}";
+
+/// Runs pulse. Returns the JSON result when `json` is set (the caller is
+/// responsible for printing it, so multi-repo mode can wrap it with a repo
+/// label); otherwise prints the colored output itself and returns `None`.
+pub fn run(repo_path: &Path, rank_by_lines: bool, json: bool) -> Result<Option<serde_json::Value>> {
     let metric = if rank_by_lines { "lines changed" } else { "commits" };
-    println!("{}", format!("📊 Gathering contributor statistics by {}...", metric).bright_blue());
+    if !json {
+        println!("{}", format!("📊 Gathering contributor statistics by {}...", metric).bright_blue());
+    }
 
     // check if this is a git repo
     let status = Command::new("git")
+        .current_dir(repo_path)
         .args(["rev-parse", "--is-inside-work-tree"])
         .output()
         .context("Failed to check git repository status")?;
     if !status.status.success() {
-        println!("{}", "❌ Not a git repository.".red());
-        return Ok(());
+        anyhow::bail!("Not a git repository.");
     }
 
-    // Use a tuple of (name, email) as the key
-    let mut author_counts: HashMap<(String, String), usize> = HashMap::new();
-    
-    if rank_by_lines {
-        // Get lines changed per author with email
-        let output = Command::new("git")
-            .args(["log", "--format=%aN<%aE>", "--numstat"])
-            .output()
-            .context("Failed to get git log output")?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let lines: Vec<&str> = stdout.lines().collect();
-        
-        if lines.is_empty() {
-            println!("{}", "⚠️ No commits found in this repository.".yellow());
-            return Ok(());
-        }
-        
-        let bar = ProgressBar::new(lines.len() as u64);
-        bar.set_style(
-            ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} processing lines")
-                .unwrap()
-                .progress_chars("#>-"),
-        );
-        
-        let mut current_author = (String::new(), String::new());
-        
-        for line in lines {
-            bar.inc(1);
-            
-            if line.trim().is_empty() {
-                continue;
-            }
-            
-            // If line doesn't start with a digit, it's an author name with email
-            if !line.chars().next().map_or(false, |c| c.is_numeric() || c == '-') && !line.trim().is_empty() {
-                // Parse author name and email
-                if let Some((name, email)) = parse_author_email(line.trim()) {
-                    current_author = (name, email);
-                }
-                continue;
-            }
-            
-            // Parse lines added/removed
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 && !current_author.0.is_empty() {
-                let added: usize = parts[0].parse().unwrap_or(0);
-                let removed: usize = parts[1].parse().unwrap_or(0);
-                
-                // Sum of lines added and removed
-                let lines_changed = added + removed;
-                *author_counts.entry(current_author.clone()).or_insert(0) += lines_changed;
-            }
+    // One record per commit: author header, full body (for Co-authored-by
+    // trailers), then its --numstat rows. Git already applies .mailmap to
+    // %aN/%aE for us.
+    let format = format!("--format={}%aN<%aE>{}%B{}", RECORD_SEP, BODY_SEP, END_SEP);
+    let output = Command::new("git")
+        .current_dir(repo_path)
+        .args(["log", "--numstat", &format])
+        .output()
+        .context("Failed to get git log output")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let records: Vec<&str> = stdout.split(RECORD_SEP).filter(|r| !r.is_empty()).collect();
+
+    if records.is_empty() {
+        if json {
+            return Ok(Some(serde_json::Value::Array(Vec::new())));
         }
-        
-        bar.finish_and_clear();
+        println!("{}", "⚠️ No commits found in this repository.".yellow());
+        return Ok(None);
+    }
+
+    let bar = if json {
+        ProgressBar::hidden()
     } else {
-        // Get commit counts per author with email
-        let output = Command::new("git")
-            .args(["log", "--pretty=%aN<%aE>"])
-            .output()
-            .context("Failed to get git log output")?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let lines: Vec<&str> = stdout.lines().collect();
-        let total = lines.len();
-
-        if total == 0 {
-            println!("{}", "⚠️ No commits found in this repository.".yellow());
-            return Ok(());
-        }
+        ProgressBar::new(records.len() as u64)
+    };
+    bar.set_style(
+        ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} processing commits")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
 
-        // count commits per author
-        let bar = ProgressBar::new(total as u64);
-        bar.set_style(
-            ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} processing commits")
-                .unwrap()
-                .progress_chars("#>-"),
-        );
+    // Use a tuple of (name, email) as the key
+    let mut author_counts: HashMap<(String, String), usize> = HashMap::new();
 
-        for line in lines {
-            if let Some((name, email)) = parse_author_email(line) {
-                *author_counts.entry((name, email)).or_insert(0) += 1;
-            }
-            bar.inc(1);
+    for record in records {
+        bar.inc(1);
+
+        let Some((header, rest)) = record.split_once(BODY_SEP) else { continue };
+        let Some((body, numstat)) = rest.split_once(END_SEP) else { continue };
+        let Some(author) = parse_author_email(header) else { continue };
+
+        let contribution = if rank_by_lines {
+            numstat
+                .lines()
+                .filter_map(|line| {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    if parts.len() < 2 {
+                        return None;
+                    }
+                    let added: usize = parts[0].parse().unwrap_or(0);
+                    let removed: usize = parts[1].parse().unwrap_or(0);
+                    Some(added + removed)
+                })
+                .sum()
+        } else {
+            1
+        };
+
+        *author_counts.entry(author).or_insert(0) += contribution;
+
+        // Credit co-authors too, so pair-programmed and squash-merged work shows up.
+        for co_author in parse_co_authors(body) {
+            *author_counts.entry(co_author).or_insert(0) += contribution;
         }
-        
-        bar.finish_and_clear();
     }
 
-    // sort by commit count descending
-    let mut authors: Vec<((String, String), usize)> = author_counts.into_iter().collect();
-    authors.sort_by(|a, b| b.1.cmp(&a.1));
+    bar.finish_and_clear();
+
+    // sort by contribution descending
+    let mut authors: Vec<((String, String), usize)> = coalesce_identities(author_counts);
+    authors.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    if json {
+        let metric_name = if rank_by_lines { "lines" } else { "commits" };
+        let rows: Vec<_> = authors
+            .iter()
+            .enumerate()
+            .map(|(i, ((name, email), count))| {
+                serde_json::json!({
+                    "rank": i + 1,
+                    "name": name,
+                    "email": email,
+                    "commits_or_lines": count,
+                    "metric": metric_name,
+                })
+            })
+            .collect();
+        return Ok(Some(serde_json::Value::Array(rows)));
+    }
 
     println!("{}", "👥 Top contributors:\n".bright_blue());
-    
+
     // Print table header
     println!(
         "{:<5} {:<25} {:<25} {:<32} {}",
@@ -123,23 +132,23 @@ pub fn run(rank_by_lines: bool) -> Result<()> {
         "CONTRIBUTION".bold().bright_magenta(),
         if rank_by_lines { "LINES" } else { "COMMITS" }.bold().bright_magenta()
     );
-    
+
     // Print separator line
     println!("{}", "─".repeat(100).dimmed());
-    
+
     // Print table rows
     for (i, ((name, email), count)) in authors.iter().enumerate() {
         let rank = format!("{:>2}", i + 1);
         let bar_len = (*count as f64 / authors[0].1 as f64 * 30.0).round() as usize;
         let bar = "█".repeat(bar_len);
-        
+
         // Truncate email if too long
         let email_display = if email.len() > 25 {
             format!("{:.22}...", email)
         } else {
             email.clone()
         };
-        
+
         println!(
             "{:<5} {:<25} {:<25} {:<32} {}",
             rank.bright_yellow(),
@@ -152,7 +161,98 @@ pub fn run(rank_by_lines: bool) -> Result<()> {
 
     println!("\n{}", "✅ Done. Repo pulse updated.".green());
 
-    Ok(())
+    Ok(None)
+}
+
+/// Merge rows that share a normalized email, or failing that an identical
+/// name. `.mailmap` (applied by git itself to %aN/%aE) already collapses the
+/// aliases it knows about; this catches what's left, like the same person
+/// committing under a slightly different email casing with no mailmap entry.
+///
+/// Uses union-find over a deterministically sorted row order so the result
+/// (which rows end up merged, and which name/email represents the merged
+/// group) doesn't depend on `HashMap`'s randomized iteration order.
+fn coalesce_identities(counts: HashMap<(String, String), usize>) -> Vec<((String, String), usize)> {
+    let mut entries: Vec<((String, String), usize)> = counts.into_iter().collect();
+    entries.sort();
+
+    let mut dsu = DisjointSet::new(entries.len());
+    let mut by_email: HashMap<String, usize> = HashMap::new();
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+
+    for (i, ((name, email), _)) in entries.iter().enumerate() {
+        let normalized_email = email.trim().to_lowercase();
+        if !normalized_email.is_empty() {
+            match by_email.get(&normalized_email) {
+                Some(&j) => dsu.union(i, j),
+                None => {
+                    by_email.insert(normalized_email, i);
+                }
+            }
+        }
+
+        match by_name.get(name) {
+            Some(&j) => dsu.union(i, j),
+            None => {
+                by_name.insert(name.clone(), i);
+            }
+        }
+    }
+
+    let mut groups: Vec<Option<(String, String, usize)>> = vec![None; entries.len()];
+    for (i, ((name, email), count)) in entries.into_iter().enumerate() {
+        let root = dsu.find(i);
+        match &mut groups[root] {
+            Some((_, _, total)) => *total += count,
+            slot @ None => *slot = Some((name, email, count)),
+        }
+    }
+
+    groups
+        .into_iter()
+        .flatten()
+        .map(|(name, email, count)| ((name, email), count))
+        .collect()
+}
+
+/// Minimal union-find used to merge author rows into identity groups
+/// regardless of the order they're visited in.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra] = rb;
+        }
+    }
+}
+
+/// Parse `Co-authored-by: Name <email>` trailers out of a commit body.
+fn parse_co_authors(body: &str) -> Vec<(String, String)> {
+    body.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let prefix = line.get(.."Co-authored-by:".len())?;
+            if !prefix.eq_ignore_ascii_case("Co-authored-by:") {
+                return None;
+            }
+            parse_author_email(line[prefix.len()..].trim())
+        })
+        .collect()
 }
 
 // Helper function to parse author name and email from git log format
@@ -167,7 +267,90 @@ fn parse_author_email(input: &str) -> Option<(String, String)> {
             }
         }
     }
-    
+
     // Fallback if email not found
     Some((input.to_string(), "".to_string()))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(rows: &[(&str, &str, usize)]) -> HashMap<(String, String), usize> {
+        rows.iter()
+            .map(|(name, email, count)| ((name.to_string(), email.to_string()), *count))
+            .collect()
+    }
+
+    #[test]
+    fn rows_sharing_only_an_email_merge() {
+        let merged = coalesce_identities(counts(&[
+            ("Alice", "alice@example.com", 3),
+            ("Al", "alice@example.com", 2),
+        ]));
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].1, 5);
+    }
+
+    #[test]
+    fn rows_sharing_only_a_name_merge() {
+        let merged = coalesce_identities(counts(&[
+            ("Alice", "alice@work.com", 3),
+            ("Alice", "alice@personal.com", 2),
+        ]));
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].1, 5);
+    }
+
+    #[test]
+    fn transitive_chain_via_email_then_name_merges_into_one_group() {
+        // A and B share an email, B and C share a name, so all three should
+        // end up in one group even though A and C have nothing directly in
+        // common. This is the transitivity case that the original greedy,
+        // iteration-order-dependent pass got wrong.
+        let merged = coalesce_identities(counts(&[
+            ("Alice", "alice@a.com", 1),
+            ("Alice", "alice@b.com", 2),
+            ("Alicia", "alice@a.com", 4),
+        ]));
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].1, 7);
+    }
+
+    #[test]
+    fn unrelated_rows_stay_separate() {
+        let merged = coalesce_identities(counts(&[
+            ("Alice", "alice@example.com", 3),
+            ("Bob", "bob@example.com", 2),
+        ]));
+
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn parse_co_authors_reads_multiple_trailers() {
+        let body = "Fix the thing\n\nCo-authored-by: Alice <alice@example.com>\nCo-authored-by: Bob <bob@example.com>\n";
+
+        let co_authors = parse_co_authors(body);
+
+        assert_eq!(
+            co_authors,
+            vec![
+                ("Alice".to_string(), "alice@example.com".to_string()),
+                ("Bob".to_string(), "bob@example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_co_authors_is_case_insensitive() {
+        let body = "Fix the thing\n\nco-authored-by: Alice <alice@example.com>\n";
+
+        let co_authors = parse_co_authors(body);
+
+        assert_eq!(co_authors, vec![("Alice".to_string(), "alice@example.com".to_string())]);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::process::Command;
+
+#[derive(Default)]
+struct StatusCounts {
+    staged: usize,
+    modified: usize,
+    deleted: usize,
+    unmerged: usize,
+    untracked: usize,
+}
+
+/// Runs status. Returns the JSON result when `json` is set (the caller is
+/// responsible for printing it); otherwise prints the colored dashboard
+/// itself and returns `None`.
+pub fn run(json: bool) -> Result<Option<serde_json::Value>> {
+    if !json {
+        println!("{}", "🧘 Checking repo health...".bright_blue());
+    }
+
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch", "-z"])
+        .output()
+        .context("Failed to run git status")?;
+
+    if !output.status.success() {
+        anyhow::bail!("Not a git repository.");
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let entries: Vec<&str> = stdout.split('\0').filter(|e| !e.is_empty()).collect();
+
+    let mut branch = None;
+    let mut ahead = 0i64;
+    let mut behind = 0i64;
+    let mut counts = StatusCounts::default();
+
+    for entry in entries {
+        if let Some(rest) = entry.strip_prefix("# branch.head ") {
+            branch = Some(rest.to_string());
+        } else if let Some(rest) = entry.strip_prefix("# branch.ab ") {
+            for part in rest.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if let Some(rest) = entry.strip_prefix("1 ") {
+            count_xy(rest, &mut counts);
+        } else if let Some(rest) = entry.strip_prefix("2 ") {
+            count_xy(rest, &mut counts);
+        } else if entry.starts_with("u ") {
+            counts.unmerged += 1;
+        } else if entry.starts_with("? ") {
+            counts.untracked += 1;
+        }
+    }
+
+    let branch = branch.unwrap_or_else(|| "(unknown)".to_string());
+
+    let at_peace = counts.staged == 0
+        && counts.modified == 0
+        && counts.deleted == 0
+        && counts.unmerged == 0
+        && counts.untracked == 0;
+
+    if json {
+        return Ok(Some(serde_json::json!({
+            "branch": branch,
+            "ahead": ahead,
+            "behind": behind,
+            "staged": counts.staged,
+            "modified": counts.modified,
+            "deleted": counts.deleted,
+            "unmerged": counts.unmerged,
+            "untracked": counts.untracked,
+            "at_peace": at_peace,
+        })));
+    }
+
+    println!("{}", "🌿 Branch".bright_blue());
+    println!(
+        "  {} {} {}",
+        branch.bright_white().bold(),
+        if ahead > 0 { format!("↑{}", ahead).green().to_string() } else { String::new() },
+        if behind > 0 { format!("↓{}", behind).red().to_string() } else { String::new() },
+    );
+
+    println!("\n{}", "📋 Working tree".bright_blue());
+    print_count("📦", "staged", counts.staged);
+    print_count("✏️", "modified", counts.modified);
+    print_count("🗑️", "deleted", counts.deleted);
+    print_count("⚠️", "unmerged", counts.unmerged);
+    print_count("❓", "untracked", counts.untracked);
+
+    if at_peace {
+        println!("\n{}", "✅ Your repo is at peace.".green());
+    } else {
+        println!("\n{}", "🔔 Your repo has work in progress.".yellow());
+    }
+
+    Ok(None)
+}
+
+fn count_xy(rest: &str, counts: &mut StatusCounts) {
+    let mut chars = rest.chars();
+    let x = chars.next().unwrap_or('.');
+    let y = chars.next().unwrap_or('.');
+
+    if x != '.' {
+        counts.staged += 1;
+    }
+
+    match y {
+        'D' => counts.deleted += 1,
+        '.' => {}
+        _ => counts.modified += 1,
+    }
+}
+
+fn print_count(emoji: &str, label: &str, count: usize) {
+    let line = format!("  {} {:<10} {}", emoji, label, count);
+    if count == 0 {
+        println!("{}", line.dimmed());
+    } else {
+        println!("{}", line.bright_white());
+    }
+}
@@ -1,16 +1,22 @@
-use anyhow::{Result, Context};
+use anyhow::{bail, Result, Context};
 use walkdir::WalkDir;
 use std::fs;
 use std::io::Write;
+use std::path::Path;
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 
-pub fn run() -> Result<()> {
-    println!("{}", "🧹 Scanning for node_modules folders...".bright_blue());
+/// Runs the sweep. Returns the JSON result when `json` is set (the caller is
+/// responsible for printing it, so multi-repo mode can wrap it with a repo
+/// label); otherwise prints the colored output itself and returns `None`.
+pub fn run(repo_path: &Path, json: bool, assume_yes: bool) -> Result<Option<serde_json::Value>> {
+    if !json {
+        println!("{}", "🧹 Scanning for node_modules folders...".bright_blue());
+    }
 
     // Collect only top-level node_modules folders in a single pass
     let mut targets = Vec::new();
-    for entry in WalkDir::new(".")
+    for entry in WalkDir::new(repo_path)
         .follow_links(false)
         .into_iter()
         .filter_map(|e| e.ok())
@@ -20,10 +26,10 @@ pub fn run() -> Result<()> {
             // Check if this node_modules is inside another node_modules
             let is_nested = path.ancestors()
                 .skip(1) // Skip the current directory itself
-                .any(|ancestor| 
+                .any(|ancestor|
                     ancestor.file_name().map(|n| n == "node_modules").unwrap_or(false)
                 );
-            
+
             if !is_nested {
                 targets.push(path.to_path_buf());
             }
@@ -31,62 +37,103 @@ pub fn run() -> Result<()> {
     }
 
     if targets.is_empty() {
+        if json {
+            return Ok(Some(serde_json::json!({"targets": [], "bytes_reclaimed": 0})));
+        }
         println!("{}", "✅ No node_modules folders found.".green());
-        return Ok(());
+        return Ok(None);
     }
 
-    println!("{}", format!("Found {} folders to delete:", targets.len()).yellow());
-    
-    // Display folders as a tree
-    let current_dir = std::env::current_dir()?;
-    for (i, path) in targets.iter().enumerate() {
-        // Try to get relative path for cleaner display
-        let display_path = path.strip_prefix(&current_dir)
-            .unwrap_or(path)
-            .display()
-            .to_string();
-        
-        if i == targets.len() - 1 {
-            println!("└── {}", display_path.bright_white());
-        } else {
-            println!("├── {}", display_path.bright_white());
+    let display_targets: Vec<String> = targets
+        .iter()
+        .map(|p| p.strip_prefix(repo_path).unwrap_or(p).display().to_string())
+        .collect();
+
+    if !json {
+        println!("{}", format!("Found {} folders to delete:", targets.len()).yellow());
+
+        // Display folders as a tree
+        for (i, display_path) in display_targets.iter().enumerate() {
+            if i == display_targets.len() - 1 {
+                println!("└── {}", display_path.bright_white());
+            } else {
+                println!("├── {}", display_path.bright_white());
+            }
         }
     }
-    
-    // Ask for confirmation
-    print!("{}", "\nDo you want to delete these folders? [y/N]: ".bright_yellow());
-    std::io::stdout().flush().unwrap(); // Ensure prompt is displayed before reading input
-    
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input)?;
-    
-    if !input.trim().eq_ignore_ascii_case("y") {
-        println!("{}", "Operation cancelled.".yellow());
-        return Ok(());
+
+    if !assume_yes {
+        // A prompt on stdout would corrupt machine-readable output and hang
+        // non-interactive callers, so --json requires --yes instead of asking.
+        if json {
+            bail!("--json requires --yes for destructive commands (no interactive prompt in JSON mode)");
+        }
+
+        // Ask for confirmation
+        print!("{}", "\nDo you want to delete these folders? [y/N]: ".bright_yellow());
+        std::io::stdout().flush().unwrap(); // Ensure prompt is displayed before reading input
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{}", "Operation cancelled.".yellow());
+            return Ok(None);
+        }
     }
-    
+
+    // Tally reclaimed size before we delete anything
+    let bytes_reclaimed: u64 = targets.iter().map(|p| dir_size(p)).sum();
+
     // Delete deepest paths first to avoid conflicts
     targets.sort_by_key(|p| std::cmp::Reverse(p.components().count()));
 
-    let bar = ProgressBar::new(targets.len() as u64);
-    bar.set_style(
-        ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
+    let bar = if json {
+        None
+    } else {
+        let bar = ProgressBar::new(targets.len() as u64);
+        bar.set_style(
+            ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        Some(bar)
+    };
 
     for path in &targets {
         let display = path.display().to_string();
-        bar.set_message(format!("Removing {}", display));
+        if let Some(bar) = &bar {
+            bar.set_message(format!("Removing {}", display));
+        }
         if path.exists() {
             fs::remove_dir_all(path)
                 .with_context(|| format!("Failed to delete {}", display))?;
         }
-        bar.inc(1);
+        if let Some(bar) = &bar {
+            bar.inc(1);
+        }
     }
 
-    bar.finish_with_message("✨ All node_modules folders removed!");
+    if json {
+        return Ok(Some(serde_json::json!({
+            "targets": display_targets,
+            "bytes_reclaimed": bytes_reclaimed,
+        })));
+    }
+
+    bar.unwrap().finish_with_message("✨ All node_modules folders removed!");
     println!("{}", "✅ Done. Your repo is now lighter.".green());
 
-    Ok(())
-}
\ No newline at end of file
+    Ok(None)
+}
+
+/// Sum the size of every file under `path`, used to report bytes reclaimed.
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the config file zen looks for in the current directory.
+pub const CONFIG_FILE: &str = "zen.toml";
+
+#[derive(Debug, Deserialize)]
+struct RawConfig {
+    repositories: RawRepositories,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRepositories {
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// A single repository to operate on, expanded from a `zen.toml` entry.
+#[derive(Debug, Clone)]
+pub struct Repo {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+}
+
+impl Repo {
+    /// A short label for headers, e.g. `/home/user/code/api:develop`. `path`
+    /// is already `~`-expanded by the time a `Repo` exists, so a `~` entry
+    /// prints as the expanded home path, never the original `~`-relative form;
+    /// other entries (including relative ones) print as written.
+    pub fn label(&self) -> String {
+        match &self.branch {
+            Some(branch) => format!("{}:{}", self.path.display(), branch),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    /// If this entry pinned a branch (`path:branch`), check that the repo is
+    /// actually on it. Returns `Ok(())` when there's nothing to check or the
+    /// branch matches, and an error naming the mismatch otherwise, so a
+    /// `zen.toml` pin isn't just a cosmetic label.
+    pub fn check_branch(&self) -> Result<()> {
+        let Some(expected) = &self.branch else {
+            return Ok(());
+        };
+
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .output()
+            .with_context(|| format!("Failed to check current branch of {}", self.path.display()))?;
+
+        if !output.status.success() {
+            anyhow::bail!("{} is not a git repository", self.path.display());
+        }
+
+        let actual = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if &actual != expected {
+            anyhow::bail!(
+                "expected branch '{}' but {} is on '{}'",
+                expected,
+                self.path.display(),
+                actual
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Load and expand the repository list from `zen.toml` in the current directory.
+/// Returns `Ok(None)` when no config file is present, so callers can fall back
+/// to single-repo mode.
+pub fn load() -> Result<Option<Vec<Repo>>> {
+    let config_path = Path::new(CONFIG_FILE);
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", CONFIG_FILE))?;
+    let raw: RawConfig = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", CONFIG_FILE))?;
+
+    let excluded: Vec<PathBuf> = raw
+        .repositories
+        .exclude
+        .iter()
+        .map(|p| expand_home(p))
+        .collect();
+
+    let repos: Vec<Repo> = raw
+        .repositories
+        .paths
+        .iter()
+        .map(|entry| parse_entry(entry))
+        .filter(|repo| !excluded.contains(&repo.path))
+        .collect();
+
+    Ok(Some(repos))
+}
+
+/// Parse a `path` or `path:branch` entry into a `Repo`, expanding `~`.
+fn parse_entry(entry: &str) -> Repo {
+    match entry.rsplit_once(':') {
+        Some((path, branch)) => Repo {
+            path: expand_home(path),
+            branch: Some(branch.to_string()),
+        },
+        None => Repo {
+            path: expand_home(entry),
+            branch: None,
+        },
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    } else if path == "~" {
+        if let Some(home) = dirs::home_dir() {
+            return home;
+        }
+    }
+
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_entry_splits_path_and_branch() {
+        let repo = parse_entry("/code/api:develop");
+
+        assert_eq!(repo.path, PathBuf::from("/code/api"));
+        assert_eq!(repo.branch.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn parse_entry_without_branch_has_no_branch() {
+        let repo = parse_entry("/code/api");
+
+        assert_eq!(repo.path, PathBuf::from("/code/api"));
+        assert_eq!(repo.branch, None);
+    }
+
+    #[test]
+    fn parse_entry_splits_on_the_last_colon() {
+        // A Windows-style drive path has its own colon; only the trailing
+        // `:branch` should be treated as a branch pin.
+        let repo = parse_entry("C:/code/api:develop");
+
+        assert_eq!(repo.path, PathBuf::from("C:/code/api"));
+        assert_eq!(repo.branch.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn expand_home_rewrites_tilde_slash_prefix() {
+        let home = dirs::home_dir().expect("test environment must have a home dir");
+
+        assert_eq!(expand_home("~/code/api"), home.join("code/api"));
+    }
+
+    #[test]
+    fn expand_home_rewrites_bare_tilde() {
+        let home = dirs::home_dir().expect("test environment must have a home dir");
+
+        assert_eq!(expand_home("~"), home);
+    }
+
+    #[test]
+    fn expand_home_leaves_absolute_paths_alone() {
+        assert_eq!(expand_home("/code/api"), PathBuf::from("/code/api"));
+    }
+
+    #[test]
+    fn label_formats_path_and_branch() {
+        let repo = Repo { path: PathBuf::from("/code/api"), branch: Some("develop".to_string()) };
+        assert_eq!(repo.label(), "/code/api:develop");
+
+        let repo = Repo { path: PathBuf::from("/code/api"), branch: None };
+        assert_eq!(repo.label(), "/code/api");
+    }
+
+    #[test]
+    fn exclude_filters_entries_that_expand_to_the_same_path() {
+        let raw = RawConfig {
+            repositories: RawRepositories {
+                paths: vec!["/code/api".to_string(), "/code/web".to_string()],
+                exclude: vec!["/code/api".to_string()],
+            },
+        };
+
+        let excluded: Vec<PathBuf> = raw.repositories.exclude.iter().map(|p| expand_home(p)).collect();
+        let repos: Vec<Repo> = raw
+            .repositories
+            .paths
+            .iter()
+            .map(|entry| parse_entry(entry))
+            .filter(|repo| !excluded.contains(&repo.path))
+            .collect();
+
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].path, PathBuf::from("/code/web"));
+    }
+
+    #[test]
+    fn exclude_requires_matching_path_components() {
+        // `exclude` filters by exact PathBuf equality, so an entry that
+        // doesn't expand to the exact same form (e.g. a "./"-relative entry
+        // matched against an equivalent bare-relative path) is silently kept
+        // rather than excluded. Documented here so a future change to this
+        // comparison is a deliberate one.
+        let raw = RawConfig {
+            repositories: RawRepositories {
+                paths: vec!["api".to_string()],
+                exclude: vec!["./api".to_string()],
+            },
+        };
+
+        let excluded: Vec<PathBuf> = raw.repositories.exclude.iter().map(|p| expand_home(p)).collect();
+        let repos: Vec<Repo> = raw
+            .repositories
+            .paths
+            .iter()
+            .map(|entry| parse_entry(entry))
+            .filter(|repo| !excluded.contains(&repo.path))
+            .collect();
+
+        assert_eq!(repos.len(), 1);
+    }
+}
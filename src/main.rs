@@ -1,37 +1,142 @@
 mod commands;
+mod config;
 
 use clap::{Parser, Subcommand};
 use anyhow::Result;
+use colored::*;
+use std::path::Path;
 
 #[derive(Parser)]
 #[command(name = "zen", about = "Keep your repos at peace 🧘‍♂️", author, version, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Emit machine-readable JSON instead of colored output
+    #[arg(long, global = true)]
+    json: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Delete all node_modules folders recursively
-    Sweep,
+    Sweep {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
     /// Delete local branches that no longer exist on origin
-    Prune,
+    Prune {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
     /// Show contributors ranked by number of commits
     Pulse {
         /// Rank by lines changed instead of commit count
         #[arg(short, long)]
         lines: bool,
     },
+    /// Show a compact health summary of the working tree
+    Status,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    let json = cli.json;
 
     match cli.command {
-        Commands::Sweep => commands::sweep::run()?,
-        Commands::Prune => commands::prune::run()?,
-        Commands::Pulse { lines } => commands::pulse::run(lines)?,
+        Commands::Sweep { yes } => run_over_repos(json, |path| commands::sweep::run(path, json, yes)),
+        Commands::Prune { yes } => run_over_repos(json, |path| commands::prune::run(path, json, yes)),
+        Commands::Pulse { lines } => run_over_repos(json, |path| commands::pulse::run(path, lines, json)),
+        Commands::Status => {
+            if let Some(value) = commands::status::run(json)? {
+                println!("{}", value);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Run a single-repo command once per repo listed in `zen.toml`, or once
+/// against the current directory when no config file is present. This is
+/// what turns `sweep`/`prune`/`pulse` into fleet-wide commands.
+///
+/// In `--json` mode, each repo's result is wrapped with its label so a
+/// fleet's output can be told apart, and any repo failures are surfaced both
+/// in that JSON and via a non-zero exit code.
+fn run_over_repos(
+    json: bool,
+    mut f: impl FnMut(&Path) -> Result<Option<serde_json::Value>>,
+) -> Result<()> {
+    let repos = match config::load()? {
+        Some(repos) => repos,
+        None => {
+            let result = f(Path::new("."))?;
+            if let Some(value) = result {
+                println!("{}", value);
+            }
+            return Ok(());
+        }
+    };
+
+    if repos.is_empty() {
+        if json {
+            println!("{}", serde_json::Value::Array(Vec::new()));
+        } else {
+            println!("{}", "⚠️ No repositories configured in zen.toml.".yellow());
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    let mut json_results = Vec::new();
+
+    for repo in &repos {
+        if !json {
+            println!(
+                "\n{}",
+                format!("📁 {}", repo.label()).bright_magenta().bold()
+            );
+        }
+
+        match repo.check_branch().and_then(|_| f(&repo.path)) {
+            Ok(result) => {
+                if json {
+                    json_results.push(serde_json::json!({
+                        "repo": repo.label(),
+                        "ok": true,
+                        "result": result,
+                    }));
+                }
+            }
+            Err(err) => {
+                failed += 1;
+                if json {
+                    json_results.push(serde_json::json!({
+                        "repo": repo.label(),
+                        "ok": false,
+                        "error": err.to_string(),
+                    }));
+                } else {
+                    println!("{} {}", "❌".red(), err);
+                }
+            }
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::Value::Array(json_results));
+    } else {
+        println!(
+            "\n{}",
+            format!("🧘 Processed {} repositories ({} failed).", repos.len(), failed).bright_blue()
+        );
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} repositories failed", failed, repos.len());
     }
 
     Ok(())
-}
\ No newline at end of file
+}